@@ -1,26 +1,49 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-use reqwest::StatusCode;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use reqwest::{Certificate, Identity, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::RwLock;
 
-use crate::types::{CreateDeploymentBody, Deployment, LoginCredentials};
+use crate::error::MonitorError;
+use crate::types::{
+    Build, ContainerState, CreateBuildBody, CreateDeploymentBody, CreateServerBody, Deployment,
+    DeploymentStatus, LoginCredentials, Server,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Client {
     url: String,
-    token: String,
+    token: Arc<RwLock<String>>,
+    credentials: Option<LoginCredentials>,
     http_client: reqwest::Client,
 }
 
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("url", &self.url)
+            .field("token", &"[redacted]")
+            .field("credentials", &self.credentials)
+            .field("http_client", &self.http_client)
+            .finish()
+    }
+}
+
 impl Client {
-    pub async fn new(url: &str, username: &str, password: &str) -> Client {
+    pub async fn new(url: &str, username: &str, password: &str) -> Result<Client, MonitorError> {
         let http_client = reqwest::Client::new();
-        let url = Client::parse_url(url);
-        Client {
-            url: url.to_string(),
-            token: Client::login(&http_client, url, username, password).await,
+        let url = Client::parse_url(url).to_string();
+        let token = Client::login(&http_client, &url, username, password).await?;
+        Ok(Client {
+            url,
+            token: Arc::new(RwLock::new(token)),
+            credentials: Some(LoginCredentials::new(username, password)),
             http_client,
-        }
+        })
     }
 
     pub fn new_with_token(url: &str, token: &str) -> Client {
@@ -28,11 +51,16 @@ impl Client {
         let url = Client::parse_url(url).to_string();
         Client {
             url,
-            token: token.to_string(),
+            token: Arc::new(RwLock::new(token.to_string())),
+            credentials: None,
             http_client,
         }
     }
 
+    pub fn builder(url: &str) -> ClientBuilder {
+        ClientBuilder::new(url)
+    }
+
     fn parse_url(url: &str) -> &str {
         if url.chars().nth(url.len() - 1).unwrap() == '/' {
             &url[..url.len() - 1]
@@ -44,7 +72,7 @@ impl Client {
     pub async fn create_deployment(
         &self,
         deployment: Deployment,
-    ) -> Result<Deployment, String> {
+    ) -> Result<Deployment, MonitorError> {
         self.post::<CreateDeploymentBody, Deployment>(
             "/api/deployment/create",
             deployment.into_create_body(),
@@ -52,29 +80,142 @@ impl Client {
         .await
     }
 
-    pub async fn deploy(&self, deployment_id: &str) -> Result<String, String> {
+    pub async fn deploy(&self, deployment_id: &str) -> Result<String, MonitorError> {
         self.get_string(&format!("/api/deployment/{deployment_id}/deploy"))
             .await
     }
 
-    pub async fn get_deployment(&self, deployment_id: &str) -> Result<Deployment, String> {
+    pub async fn get_deployment(&self, deployment_id: &str) -> Result<Deployment, MonitorError> {
         self.get(&format!("/api/deployment/{deployment_id}")).await
     }
 
-    pub async fn delete_deployment(&self, deployment_id: &str) -> Result<String, String> {
+    pub async fn delete_deployment(&self, deployment_id: &str) -> Result<String, MonitorError> {
         self.delete_string(&format!("/api/deployment/{deployment_id}/delete"))
             .await
     }
 
-    pub async fn get_deployments(&self) -> Result<HashMap<String, Deployment>, String> {
+    pub async fn get_deployments(&self) -> Result<HashMap<String, Deployment>, MonitorError> {
         self.get("/api/deployments").await
     }
 
+    pub async fn get_deployment_status(
+        &self,
+        deployment_id: &str,
+    ) -> Result<DeploymentStatus, MonitorError> {
+        self.get(&format!("/api/deployment/{deployment_id}/status"))
+            .await
+    }
+
+    pub async fn list_deployment_statuses(
+        &self,
+        deployment_id: &str,
+    ) -> Result<Vec<DeploymentStatus>, MonitorError> {
+        self.get(&format!("/api/deployment/{deployment_id}/status/history"))
+            .await
+    }
+
+    pub async fn get_deployment_logs(
+        &self,
+        deployment_id: &str,
+        tail: impl Into<Option<usize>>,
+    ) -> Result<String, MonitorError> {
+        let endpoint = match tail.into() {
+            Some(tail) => format!("/api/deployment/{deployment_id}/logs?tail={tail}"),
+            None => format!("/api/deployment/{deployment_id}/logs"),
+        };
+        self.get_string(&endpoint).await
+    }
+
+    pub fn stream_deployment_logs(
+        &self,
+        deployment_id: &str,
+    ) -> impl Stream<Item = Result<String, MonitorError>> {
+        let endpoint = format!("/api/deployment/{deployment_id}/logs/stream");
+        let client = self.clone();
+
+        async_stream::stream! {
+            let res = client
+                .send_with_retry(|token| {
+                    client.http_client
+                        .get(format!("{}{endpoint}", client.url))
+                        .header("Authorization", format!("Bearer {token}"))
+                })
+                .await;
+
+            let res = match res {
+                Ok(res) => res,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+
+            let status = res.status();
+            if status != StatusCode::OK {
+                let body = res.text().await.unwrap_or_default();
+                yield Err(MonitorError::Status { code: status, body });
+                return;
+            }
+
+            let mut bytes_stream = res.bytes_stream();
+            // Raw bytes not yet known to form complete UTF-8 (e.g. a multi-byte
+            // character split across a chunk boundary). Carried over to the next
+            // chunk rather than decoded lossily. A buffer holding bytes that are
+            // invalid outright is decoded lossily as a whole at once.
+            let mut pending_bytes: Vec<u8> = Vec::new();
+            let mut line_buffer = String::new();
+
+            while let Some(chunk) = bytes_stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(MonitorError::from(e));
+                        return;
+                    }
+                };
+
+                pending_bytes.extend_from_slice(&chunk);
+
+                let valid_len = match std::str::from_utf8(&pending_bytes) {
+                    Ok(s) => s.len(),
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    Err(_) => {
+                        line_buffer.push_str(&String::from_utf8_lossy(&pending_bytes));
+                        pending_bytes.clear();
+                        0
+                    }
+                };
+                line_buffer.push_str(std::str::from_utf8(&pending_bytes[..valid_len]).unwrap());
+                pending_bytes.drain(..valid_len);
+
+                while let Some(pos) = line_buffer.find('\n') {
+                    let line = line_buffer.drain(..=pos).collect::<String>();
+                    yield Ok(line.trim_end_matches('\n').to_string());
+                }
+            }
+
+            if !pending_bytes.is_empty() {
+                line_buffer.push_str(&String::from_utf8_lossy(&pending_bytes));
+            }
+            if !line_buffer.is_empty() {
+                yield Ok(line_buffer);
+            }
+        }
+    }
+
+    pub async fn inspect_deployment(
+        &self,
+        deployment_id: &str,
+    ) -> Result<ContainerState, MonitorError> {
+        self.get(&format!("/api/deployment/{deployment_id}/inspect"))
+            .await
+    }
+
     pub async fn delete_all_deployments_on_server<Callback>(
         &self,
         server_id: &str,
         on_delete: impl Into<Option<Callback>>,
-    ) -> Result<(), String>
+    ) -> Result<(), MonitorError>
     where
         Callback: Fn(Deployment) -> (),
     {
@@ -99,151 +240,350 @@ impl Client {
         Ok(())
     }
 
-    async fn login(client: &reqwest::Client, url: &str, username: &str, password: &str) -> String {
-        client
-            .post(format!("{url}/login/local"))
-            .json(&LoginCredentials::new(username, password))
-            .send()
+    pub async fn create_server(&self, server: Server) -> Result<Server, MonitorError> {
+        self.post::<CreateServerBody, Server>("/api/server/create", server.into_create_body())
+            .await
+    }
+
+    pub async fn get_server(&self, server_id: &str) -> Result<Server, MonitorError> {
+        self.get(&format!("/api/server/{server_id}")).await
+    }
+
+    pub async fn get_servers(&self) -> Result<HashMap<String, Server>, MonitorError> {
+        self.get("/api/servers").await
+    }
+
+    pub async fn delete_server(&self, server_id: &str) -> Result<String, MonitorError> {
+        self.delete_string(&format!("/api/server/{server_id}/delete"))
+            .await
+    }
+
+    pub async fn create_build(&self, build: Build) -> Result<Build, MonitorError> {
+        self.post::<CreateBuildBody, Build>("/api/build/create", build.into_create_body())
+            .await
+    }
+
+    pub async fn get_build(&self, build_id: &str) -> Result<Build, MonitorError> {
+        self.get(&format!("/api/build/{build_id}")).await
+    }
+
+    pub async fn get_builds(&self) -> Result<HashMap<String, Build>, MonitorError> {
+        self.get("/api/builds").await
+    }
+
+    pub async fn delete_build(&self, build_id: &str) -> Result<String, MonitorError> {
+        self.delete_string(&format!("/api/build/{build_id}/delete"))
             .await
-            .unwrap()
-            .text()
+    }
+
+    pub async fn build(&self, build_id: &str) -> Result<String, MonitorError> {
+        self.get_string(&format!("/api/build/{build_id}/build"))
             .await
-            .unwrap()
     }
 
-    async fn get<R: DeserializeOwned>(&self, endpoint: &str) -> Result<R, String> {
-        let res = self.http_client
-            .get(format!("{}{endpoint}", self.url))
-            .header("Authorization", format!("Bearer {}", self.token))
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(client, username, password),
+            fields(method = "POST", endpoint = "/login/local")
+        )
+    )]
+    async fn login(
+        client: &reqwest::Client,
+        url: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<String, MonitorError> {
+        let res = client
+            .post(format!("{url}/login/local"))
+            .json(&LoginCredentials::new(username, password))
             .send()
-            .await;
-        match res {
-            Ok(res) => {
-                let status = res.status();
-                if status == StatusCode::OK {
-                    match res.json().await {
-                        Ok(res) => Ok(res),
-                        Err(e) => Err(format!("{status}: {e:#?}")),
-                    }
-                } else {
-                    match res.text().await {
-                        Ok(res) => Err(format!("{status}: {res}")),
-                        Err(e) => Err(format!("{status}: {e:#?}"))
-                    }
-                }
+            .await?;
+        let status = res.status();
+        let body = res.text().await?;
+        if status == StatusCode::OK {
+            Ok(body)
+        } else {
+            Err(MonitorError::Status { code: status, body })
+        }
+    }
+
+    async fn token(&self) -> String {
+        self.token.read().await.clone()
+    }
+
+    /// Re-runs `login()` with the credentials the client was constructed with and
+    /// stores the resulting token. Only called once `send_with_retry` has already
+    /// confirmed credentials are present.
+    async fn refresh_token(&self) -> Result<(), MonitorError> {
+        let credentials = self.credentials.as_ref().ok_or(MonitorError::Auth)?;
+        let token = Client::login(
+            &self.http_client,
+            &self.url,
+            &credentials.username,
+            &credentials.password,
+        )
+        .await?;
+        *self.token.write().await = token;
+        Ok(())
+    }
+
+    /// Sends the request built by `build`, retrying exactly once after a fresh
+    /// `login()` if the server responds `401 Unauthorized`. Clients with no
+    /// credentials to refresh with (`new_with_token`) have nothing to retry, so
+    /// the original `401` response is returned as-is.
+    async fn send_with_retry(
+        &self,
+        build: impl Fn(&str) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, MonitorError> {
+        let token = self.token().await;
+        let res = match build(&token).send().await {
+            Ok(res) => res,
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!(error = %e, "request failed to send");
+                return Err(e.into());
             }
-            Err(e) => Err(format!("{e:#?}")),
+        };
+
+        if res.status() == StatusCode::UNAUTHORIZED && self.credentials.is_some() {
+            self.refresh_token().await?;
+            let token = self.token().await;
+            return Ok(build(&token).send().await?);
         }
+
+        Ok(res)
     }
 
-    async fn get_string(&self, endpoint: &str) -> Result<String, String> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(method = "GET", endpoint = %endpoint, status = tracing::field::Empty))
+    )]
+    async fn get<R: DeserializeOwned>(&self, endpoint: &str) -> Result<R, MonitorError> {
         let res = self
-            .http_client
-            .get(format!("{}{endpoint}", self.url))
-            .header("Authorization", format!("Bearer {}", self.token))
-            .send()
-            .await;
-
-        match res {
-            Ok(res) => {
-                let status = res.status();
-                if status == StatusCode::OK {
-                    match res.text().await {
-                        Ok(res) => Ok(res),
-                        Err(e) => Err(format!("{status}: {e:#?}")),
-                    }
-                } else {
-                    match res.text().await {
-                        Ok(res) => Err(format!("{status}: {res}")),
-                        Err(e) => Err(format!("{status}: {e:#?}"))
-                    }
-                }
-            }
-            Err(e) => Err(format!("{e:#?}")),
+            .send_with_retry(|token| {
+                self.http_client
+                    .get(format!("{}{endpoint}", self.url))
+                    .header("Authorization", format!("Bearer {token}"))
+            })
+            .await?;
+
+        let status = res.status();
+        record_status(status);
+        let body = res.text().await?;
+        if status == StatusCode::OK {
+            Ok(serde_json::from_str(&body)?)
+        } else {
+            log_error_status(status, &body);
+            Err(MonitorError::Status { code: status, body })
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(method = "GET", endpoint = %endpoint, status = tracing::field::Empty))
+    )]
+    async fn get_string(&self, endpoint: &str) -> Result<String, MonitorError> {
+        let res = self
+            .send_with_retry(|token| {
+                self.http_client
+                    .get(format!("{}{endpoint}", self.url))
+                    .header("Authorization", format!("Bearer {token}"))
+            })
+            .await?;
+
+        let status = res.status();
+        record_status(status);
+        let body = res.text().await?;
+        if status == StatusCode::OK {
+            Ok(body)
+        } else {
+            log_error_status(status, &body);
+            Err(MonitorError::Status { code: status, body })
         }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, body), fields(method = "POST", endpoint = %endpoint, status = tracing::field::Empty))
+    )]
     async fn post<B: Serialize, R: DeserializeOwned>(
         &self,
         endpoint: &str,
         body: B,
-    ) -> Result<R, String> {
-        let res = self.http_client
-            .post(format!("{}{endpoint}", self.url))
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await;
-        
-        match res {
-            Ok(res) => {
-                let status = res.status();
-                if status == StatusCode::OK {
-                    match res.json().await {
-                        Ok(res) => Ok(res),
-                        Err(e) => Err(format!("{status}: {e:#?}")),
-                    }
-                } else {
-                    match res.text().await {
-                        Ok(res) => Err(format!("{status}: {res}")),
-                        Err(e) => Err(format!("{status}: {e:#?}"))
-                    }
-                }
-            }
-            Err(e) => Err(format!("{e:#?}")),
+    ) -> Result<R, MonitorError> {
+        let res = self
+            .send_with_retry(|token| {
+                self.http_client
+                    .post(format!("{}{endpoint}", self.url))
+                    .header("Authorization", format!("Bearer {token}"))
+                    .header("Content-Type", "application/json")
+                    .json(&body)
+            })
+            .await?;
+
+        let status = res.status();
+        record_status(status);
+        let body = res.text().await?;
+        if status == StatusCode::OK {
+            Ok(serde_json::from_str(&body)?)
+        } else {
+            log_error_status(status, &body);
+            Err(MonitorError::Status { code: status, body })
         }
     }
 
-    async fn delete<R: DeserializeOwned>(&self, endpoint: &str) -> Result<R, String> {
-        let res = self.http_client
-            .delete(format!("{}{endpoint}", self.url))
-            .header("Authorization", format!("Bearer {}", self.token))
-            .send()
-            .await;
-
-        match res {
-            Ok(res) => {
-                let status = res.status();
-                if status == StatusCode::OK {
-                    match res.json().await {
-                        Ok(res) => Ok(res),
-                        Err(e) => Err(format!("{status}: {e:#?}")),
-                    }
-                } else {
-                    match res.text().await {
-                        Ok(res) => Err(format!("{status}: {res}")),
-                        Err(e) => Err(format!("{status}: {e:#?}"))
-                    }
-                }
-            }
-            Err(e) => Err(format!("{e:#?}")),
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(method = "DELETE", endpoint = %endpoint, status = tracing::field::Empty))
+    )]
+    async fn delete<R: DeserializeOwned>(&self, endpoint: &str) -> Result<R, MonitorError> {
+        let res = self
+            .send_with_retry(|token| {
+                self.http_client
+                    .delete(format!("{}{endpoint}", self.url))
+                    .header("Authorization", format!("Bearer {token}"))
+            })
+            .await?;
+
+        let status = res.status();
+        record_status(status);
+        let body = res.text().await?;
+        if status == StatusCode::OK {
+            Ok(serde_json::from_str(&body)?)
+        } else {
+            log_error_status(status, &body);
+            Err(MonitorError::Status { code: status, body })
         }
     }
 
-    async fn delete_string(&self, endpoint: &str) -> Result<String, String> {
-        let res = self.http_client
-            .delete(format!("{}{endpoint}", self.url))
-            .header("Authorization", format!("Bearer {}", self.token))
-            .send()
-            .await;
-
-        match res {
-            Ok(res) => {
-                let status = res.status();
-                if status == StatusCode::OK {
-                    match res.text().await {
-                        Ok(res) => Ok(res),
-                        Err(e) => Err(format!("{status}: {e:#?}")),
-                    }
-                } else {
-                    match res.text().await {
-                        Ok(res) => Err(format!("{status}: {res}")),
-                        Err(e) => Err(format!("{status}: {e:#?}"))
-                    }
-                }
-            }
-            Err(e) => Err(format!("{e:#?}")),
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(method = "DELETE", endpoint = %endpoint, status = tracing::field::Empty))
+    )]
+    async fn delete_string(&self, endpoint: &str) -> Result<String, MonitorError> {
+        let res = self
+            .send_with_retry(|token| {
+                self.http_client
+                    .delete(format!("{}{endpoint}", self.url))
+                    .header("Authorization", format!("Bearer {token}"))
+            })
+            .await?;
+
+        let status = res.status();
+        record_status(status);
+        let body = res.text().await?;
+        if status == StatusCode::OK {
+            Ok(body)
+        } else {
+            log_error_status(status, &body);
+            Err(MonitorError::Status { code: status, body })
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn record_status(status: StatusCode) {
+    tracing::Span::current().record("status", status.as_u16());
+}
+
+#[cfg(not(feature = "tracing"))]
+fn record_status(_status: StatusCode) {}
+
+#[cfg(feature = "tracing")]
+fn log_error_status(status: StatusCode, body: &str) {
+    tracing::error!(%status, %body, "request returned a non-OK status");
+}
+
+#[cfg(not(feature = "tracing"))]
+fn log_error_status(_status: StatusCode, _body: &str) {}
+
+#[derive(Default)]
+pub struct ClientBuilder {
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    root_certificates: Vec<Certificate>,
+    identity: Option<Identity>,
+    danger_accept_invalid_certs: bool,
+    timeout: Option<Duration>,
+}
+
+impl ClientBuilder {
+    pub fn new(url: &str) -> ClientBuilder {
+        ClientBuilder {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn login(mut self, username: &str, password: &str) -> ClientBuilder {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+
+    pub fn add_root_certificate(mut self, cert: Certificate) -> ClientBuilder {
+        self.root_certificates.push(cert);
+        self
+    }
+
+    pub fn identity(mut self, identity: Identity) -> ClientBuilder {
+        self.identity = Some(identity);
+        self
+    }
+
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> ClientBuilder {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub async fn build(self) -> Result<Client, MonitorError> {
+        let mut builder = reqwest::Client::builder();
+        for cert in self.root_certificates {
+            builder = builder.add_root_certificate(cert);
         }
+        if let Some(identity) = self.identity {
+            builder = builder.identity(identity);
+        }
+        if self.danger_accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let http_client = builder.build()?;
+        let url = Client::parse_url(&self.url).to_string();
+
+        let credentials = match (&self.username, &self.password) {
+            (Some(username), Some(password)) => Some(LoginCredentials::new(username, password)),
+            _ => None,
+        };
+
+        let token = match &credentials {
+            Some(credentials) => {
+                Client::login(
+                    &http_client,
+                    &url,
+                    &credentials.username,
+                    &credentials.password,
+                )
+                .await?
+            }
+            None => String::new(),
+        };
+
+        Ok(Client {
+            url,
+            token: Arc::new(RwLock::new(token)),
+            credentials,
+            http_client,
+        })
     }
 }
@@ -0,0 +1,46 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+
+#[derive(Debug)]
+pub enum MonitorError {
+    Transport(reqwest::Error),
+    Status { code: StatusCode, body: String },
+    Deserialize(serde_json::Error),
+    Auth,
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorError::Transport(e) => write!(f, "transport error: {e}"),
+            MonitorError::Status { code, body } => write!(f, "{code}: {body}"),
+            MonitorError::Deserialize(e) => write!(f, "failed to deserialize response: {e}"),
+            MonitorError::Auth => {
+                write!(f, "401: token expired and no credentials to refresh with")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MonitorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MonitorError::Transport(e) => Some(e),
+            MonitorError::Deserialize(e) => Some(e),
+            MonitorError::Status { .. } | MonitorError::Auth => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for MonitorError {
+    fn from(e: reqwest::Error) -> MonitorError {
+        MonitorError::Transport(e)
+    }
+}
+
+impl From<serde_json::Error> for MonitorError {
+    fn from(e: serde_json::Error) -> MonitorError {
+        MonitorError::Deserialize(e)
+    }
+}
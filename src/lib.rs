@@ -0,0 +1,8 @@
+mod client;
+mod error;
+mod helpers;
+mod types;
+
+pub use client::{Client, ClientBuilder};
+pub use error::MonitorError;
+pub use types::*;
@@ -30,6 +30,12 @@ pub struct Deployment {
     pub container_user: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub docker_account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "nanoCpus")]
+    pub nano_cpus: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<HealthCheck>,
 }
 
 impl Deployment {
@@ -143,6 +149,18 @@ impl DeploymentBuilder {
         self.deployment.network = Some(network.to_string());
         self
     }
+    pub fn memory(mut self, memory: u64) -> DeploymentBuilder {
+        self.deployment.memory = Some(memory);
+        self
+    }
+    pub fn cpus(mut self, cpus: f64) -> DeploymentBuilder {
+        self.deployment.nano_cpus = Some((cpus * 1_000_000_000.0) as u64);
+        self
+    }
+    pub fn healthcheck(mut self, healthcheck: HealthCheck) -> DeploymentBuilder {
+        self.deployment.healthcheck = Some(healthcheck);
+        self
+    }
     pub fn build(self) -> Deployment {
         self.deployment
     }
@@ -161,9 +179,32 @@ pub struct EnvironmentVar {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub test: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct LoginCredentials {
-    username: String,
-    password: String,
+    pub(crate) username: String,
+    pub(crate) password: String,
+}
+
+impl std::fmt::Debug for LoginCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoginCredentials")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .finish()
+    }
 }
 
 impl LoginCredentials {
@@ -186,3 +227,150 @@ pub enum RestartMode {
     #[serde(rename = "always")]
     Always,
 }
+
+#[derive(Serialize, Deserialize, Debug, Display, EnumString, PartialEq, Hash, Eq)]
+pub enum DeploymentState {
+    Pending,
+    Deploying,
+    Running,
+    Exited,
+    Failed,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentStatus {
+    pub state: DeploymentState,
+    pub timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerState {
+    pub status: String,
+    pub running: bool,
+    pub exit_code: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Server {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+impl Server {
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::new()
+    }
+
+    pub fn into_create_body(self) -> CreateServerBody {
+        CreateServerBody { server: self }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateServerBody {
+    server: Server,
+}
+
+#[derive(Default)]
+pub struct ServerBuilder {
+    server: Server,
+}
+
+impl ServerBuilder {
+    pub fn new() -> ServerBuilder {
+        ServerBuilder {
+            ..Default::default()
+        }
+    }
+    pub fn name(mut self, name: &str) -> ServerBuilder {
+        self.server.name = name.to_string();
+        self
+    }
+    pub fn address(mut self, address: &str) -> ServerBuilder {
+        self.server.address = address.to_string();
+        self
+    }
+    pub fn region(mut self, region: impl Into<Option<String>>) -> ServerBuilder {
+        self.server.region = region.into();
+        self
+    }
+    pub fn build(self) -> Server {
+        self.server
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Build {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "dockerAccount")]
+    pub docker_account: Option<String>,
+}
+
+impl Build {
+    pub fn builder() -> BuildBuilder {
+        BuildBuilder::new()
+    }
+
+    pub fn into_create_body(self) -> CreateBuildBody {
+        CreateBuildBody { build: self }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateBuildBody {
+    build: Build,
+}
+
+#[derive(Default)]
+pub struct BuildBuilder {
+    build: Build,
+}
+
+impl BuildBuilder {
+    pub fn new() -> BuildBuilder {
+        BuildBuilder {
+            ..Default::default()
+        }
+    }
+    pub fn name(mut self, name: &str) -> BuildBuilder {
+        self.build.name = name.to_string();
+        self
+    }
+    pub fn repo(mut self, repo: impl Into<Option<String>>) -> BuildBuilder {
+        self.build.repo = repo.into();
+        self
+    }
+    pub fn branch(mut self, branch: impl Into<Option<String>>) -> BuildBuilder {
+        self.build.branch = branch.into();
+        self
+    }
+    pub fn docker_account(mut self, docker_account: impl Into<Option<String>>) -> BuildBuilder {
+        self.build.docker_account = docker_account.into();
+        self
+    }
+    pub fn build(self) -> Build {
+        self.build
+    }
+}